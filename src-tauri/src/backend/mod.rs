@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+#[cfg(target_os = "windows")]
+pub mod windows;
+
+#[cfg(target_os = "linux")]
+pub mod pulse;
+
+// Operacoes de volume abstraidas por tras de um backend especifico de cada
+// sistema. O monitor de inatividade conversa apenas com esta trait, de modo que
+// a logica de deteccao de ociosidade permaneca independente de plataforma.
+pub trait AudioController {
+    /// Volume mestre atual, normalizado em 0.0..=1.0.
+    fn current_volume(&self) -> Result<f32, String>;
+
+    /// Define o volume mestre (sera limitado a 0.0..=1.0 pelo backend).
+    fn set_volume(&self, level: f32) -> Result<(), String>;
+
+    /// Estado de mudo atual do dispositivo padrao.
+    fn is_muted(&self) -> Result<bool, String>;
+
+    /// Silencia ou reabilita o dispositivo padrao.
+    fn set_mute(&self, mute: bool) -> Result<(), String>;
+
+    /// Pico de reproducao (0.0..=1.0) em uma janela curta, usado pelo gate de
+    /// silencio. Backends sem medidor retornam 0.0.
+    fn peak_value(&self) -> Result<f32, String>;
+
+    /// Alvo de restauracao preferido quando o backend rastreia, de forma
+    /// assincrona, as mudancas externas de volume/mudo. `None` indica que o
+    /// monitor deve capturar o estado no instante em que abaixa o audio.
+    fn restore_target(&self) -> Result<Option<(f32, bool)>, String> {
+        Ok(None)
+    }
+
+    /// Aguarda ate o proximo ciclo de verificacao. Backends orientados a evento
+    /// podem retornar antes para reagir imediatamente a mudancas externas.
+    fn wait_next_poll(&self) -> Result<(), String>;
+}
+
+/// Cria o backend de audio adequado ao sistema operacional atual.
+pub fn new_controller() -> Result<Box<dyn AudioController>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        Ok(Box::new(windows::WindowsAudio::new()?))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        Ok(Box::new(pulse::PulseAudio::new()?))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        Err("Funcionalidade disponivel apenas no Windows e no Linux.".into())
+    }
+}
+
+/// Tempo desde a ultima interacao do usuario, obtido de forma nativa por
+/// plataforma.
+pub fn idle_time() -> Result<Duration, String> {
+    #[cfg(target_os = "windows")]
+    {
+        windows::idle_time()
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        pulse::idle_time()
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        Err("Funcionalidade disponivel apenas no Windows e no Linux.".into())
+    }
+}
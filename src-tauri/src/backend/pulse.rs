@@ -0,0 +1,207 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use libpulse_binding::{
+    callbacks::ListResult,
+    context::{introspect::SinkInfo, Context, FlagSet as ContextFlagSet, State as ContextState},
+    mainloop::standard::{IterateResult, Mainloop},
+    volume::{ChannelVolumes, Volume},
+};
+
+use super::AudioController;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Backend de audio para Linux apoiado no PulseAudio/PipeWire.
+///
+/// O mainloop e o contexto nao sao `Send`, mas o controlador vive inteiramente
+/// na thread do monitor, entao nao precisam ser compartilhados entre threads.
+pub struct PulseAudio {
+    mainloop: RefCell<Mainloop>,
+    context: Context,
+    sink: String,
+}
+
+impl PulseAudio {
+    pub fn new() -> Result<Self, String> {
+        let mut mainloop =
+            Mainloop::new().ok_or("Falha ao criar o mainloop do PulseAudio.")?;
+        let mut context = Context::new(&mainloop, "sound-inactivity")
+            .ok_or("Falha ao criar o contexto do PulseAudio.")?;
+
+        context
+            .connect(None, ContextFlagSet::NOFLAGS, None)
+            .map_err(|err| format!("Falha ao conectar ao PulseAudio: {err}"))?;
+
+        // Bloqueia ate o contexto ficar pronto (ou falhar).
+        loop {
+            iterate(&mut mainloop)?;
+            match context.get_state() {
+                ContextState::Ready => break,
+                ContextState::Failed | ContextState::Terminated => {
+                    return Err("Conexao com o PulseAudio falhou.".into());
+                }
+                _ => {}
+            }
+        }
+
+        let sink = default_sink_name(&mut mainloop, &context)?;
+
+        Ok(Self {
+            mainloop: RefCell::new(mainloop),
+            context,
+            sink,
+        })
+    }
+
+    // Executa uma operacao de introspeccao ate a conclusao, retornando o valor
+    // coletado pela callback.
+    fn with_sink<T, F>(&self, extract: F) -> Result<T, String>
+    where
+        F: Fn(&SinkInfo) -> T + 'static,
+        T: 'static,
+    {
+        let result: Rc<RefCell<Option<T>>> = Rc::new(RefCell::new(None));
+        let sink_result = Rc::clone(&result);
+
+        let op = self.context.introspect().get_sink_info_by_name(
+            &self.sink,
+            move |info| {
+                if let ListResult::Item(sink) = info {
+                    *sink_result.borrow_mut() = Some(extract(sink));
+                }
+            },
+        );
+
+        self.run(op)?;
+
+        result
+            .borrow_mut()
+            .take()
+            .ok_or_else(|| format!("Sink \"{}\" nao encontrado.", self.sink))
+    }
+
+    // Itera o mainloop ate a operacao terminar.
+    fn run<O: ?Sized>(&self, op: libpulse_binding::operation::Operation<O>) -> Result<(), String> {
+        let mut mainloop = self.mainloop.borrow_mut();
+        use libpulse_binding::operation::State;
+        loop {
+            iterate(&mut mainloop)?;
+            match op.get_state() {
+                State::Done => return Ok(()),
+                State::Cancelled => return Err("Operacao do PulseAudio cancelada.".into()),
+                State::Running => {}
+            }
+        }
+    }
+}
+
+impl AudioController for PulseAudio {
+    fn current_volume(&self) -> Result<f32, String> {
+        let avg = self.with_sink(|sink| sink.volume.avg())?;
+        Ok(avg.0 as f32 / Volume::NORMAL.0 as f32)
+    }
+
+    fn set_volume(&self, level: f32) -> Result<(), String> {
+        let clamped = level.clamp(0.0, 1.0);
+        let target = Volume((clamped * Volume::NORMAL.0 as f32).round() as u32);
+
+        // Preserva o numero de canais do sink atual.
+        let mut volumes: ChannelVolumes = self.with_sink(|sink| sink.volume)?;
+        volumes.set(volumes.len(), target);
+
+        let op = self
+            .context
+            .introspect()
+            .set_sink_volume_by_name(&self.sink, &volumes, None);
+        self.run(op)
+    }
+
+    fn is_muted(&self) -> Result<bool, String> {
+        self.with_sink(|sink| sink.mute)
+    }
+
+    fn set_mute(&self, mute: bool) -> Result<(), String> {
+        let op = self
+            .context
+            .introspect()
+            .set_sink_mute_by_name(&self.sink, mute, None);
+        self.run(op)
+    }
+
+    fn peak_value(&self) -> Result<f32, String> {
+        // Ler o pico exige abrir um stream de monitor do sink; como o gate de
+        // silencio e opcional, tratamos a ausencia de medidor como silencio.
+        Ok(0.0)
+    }
+
+    fn wait_next_poll(&self) -> Result<(), String> {
+        std::thread::sleep(POLL_INTERVAL);
+        Ok(())
+    }
+}
+
+/// Tempo desde a ultima interacao do usuario via extensao X11 ScreenSaver.
+pub fn idle_time() -> Result<Duration, String> {
+    use x11::{xlib, xss};
+
+    unsafe {
+        let display = xlib::XOpenDisplay(std::ptr::null());
+        if display.is_null() {
+            return Err("Falha ao abrir o display X11.".into());
+        }
+
+        let info = xss::XScreenSaverAllocInfo();
+        if info.is_null() {
+            xlib::XCloseDisplay(display);
+            return Err("Falha ao alocar XScreenSaverInfo.".into());
+        }
+
+        let root = xlib::XDefaultRootWindow(display);
+        let status = xss::XScreenSaverQueryInfo(display, root, info);
+        let idle_ms = if status != 0 { (*info).idle } else { 0 };
+
+        xlib::XFree(info as *mut _);
+        xlib::XCloseDisplay(display);
+
+        if status == 0 {
+            return Err("Falha ao consultar o XScreenSaver.".into());
+        }
+
+        Ok(Duration::from_millis(idle_ms))
+    }
+}
+
+// Itera o mainloop uma vez, propagando erros de quit/erro.
+fn iterate(mainloop: &mut Mainloop) -> Result<(), String> {
+    match mainloop.iterate(true) {
+        IterateResult::Success(_) => Ok(()),
+        IterateResult::Quit(_) => Err("Mainloop do PulseAudio encerrou.".into()),
+        IterateResult::Err(err) => Err(format!("Erro no mainloop do PulseAudio: {err}")),
+    }
+}
+
+// Descobre o nome do sink padrao a partir das informacoes do servidor.
+fn default_sink_name(mainloop: &mut Mainloop, context: &Context) -> Result<String, String> {
+    let name: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
+    let server_name = Rc::clone(&name);
+
+    let op = context.introspect().get_server_info(move |info| {
+        if let Some(sink) = &info.default_sink_name {
+            *server_name.borrow_mut() = Some(sink.to_string());
+        }
+    });
+
+    use libpulse_binding::operation::State;
+    loop {
+        iterate(mainloop)?;
+        match op.get_state() {
+            State::Done => break,
+            State::Cancelled => return Err("Consulta ao servidor PulseAudio cancelada.".into()),
+            State::Running => {}
+        }
+    }
+
+    name.borrow_mut()
+        .take()
+        .ok_or_else(|| "Nenhum sink padrao encontrado.".to_string())
+}
@@ -0,0 +1,304 @@
+use std::{
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+    time::Duration,
+};
+
+use windows::{
+    core::{implement, Error as WinError, Result as WinResult, GUID},
+    Win32::{
+        Foundation::HANDLE,
+        Media::Audio::{
+            eConsole, eRender,
+            Endpoints::{
+                IAudioEndpointVolume, IAudioEndpointVolumeCallback,
+                IAudioEndpointVolumeCallback_Impl,
+            },
+            IAudioMeterInformation, IMMDeviceEnumerator, MMDeviceEnumerator,
+            AUDIO_VOLUME_NOTIFICATION_DATA,
+        },
+        System::Com::{
+            CoCreateInstance, CoInitializeEx, CoUninitialize, CoWaitForMultipleObjects, CLSCTX_ALL,
+            COINIT_APARTMENTTHREADED, CWMO_DISPATCH_CALLS,
+        },
+        System::SystemInformation::GetTickCount64,
+        System::Threading::{CreateWaitableTimerW, SetWaitableTimer, INFINITE},
+        UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO},
+    },
+};
+
+use super::AudioController;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+// Contexto usado em todas as nossas escritas de volume/mudo; a callback de
+// notificacao o recebe de volta em `guidEventContext` e ignora essas mudancas,
+// para nao confundir nossos proprios ajustes com edicoes feitas pelo usuario.
+const SOUND_INACTIVITY_CONTEXT: GUID = GUID::from_u128(0x7f3a1c8e_4b62_4d51_9a0f_2c6e5d8b1f44);
+
+// Alvo de restauracao mantido pela callback: volume mestre (bits de f32) e
+// estado de mudo desejados pelo usuario. A callback so os atualiza quando a
+// mudanca nao veio de nos, entao permanecem validos mesmo se o usuario mexer no
+// volume enquanto o audio ja esta abaixado.
+static RESTORE_VOLUME_BITS: AtomicU32 = AtomicU32::new(0x3F80_0000); // 1.0
+static RESTORE_MUTE_STATE: AtomicBool = AtomicBool::new(false);
+
+/// Backend de audio para Windows apoiado no WASAPI.
+pub struct WindowsAudio {
+    endpoint: IAudioEndpointVolume,
+    meter: IAudioMeterInformation,
+    timer: WaitableTimer,
+    _callback: VolumeNotificationGuard,
+    _com: ComGuard,
+}
+
+impl WindowsAudio {
+    pub fn new() -> Result<Self, String> {
+        unsafe {
+            let com =
+                ComGuard::new().map_err(|err| describe_error("Falha ao inicializar COM", err))?;
+            let enumerator: IMMDeviceEnumerator =
+                CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).map_err(|err| {
+                    describe_error("Falha ao criar enumerador de dispositivos", err)
+                })?;
+            let device = enumerator
+                .GetDefaultAudioEndpoint(eRender, eConsole)
+                .map_err(|err| describe_error("Falha ao obter dispositivo de audio padrao", err))?;
+            let endpoint = device
+                .Activate::<IAudioEndpointVolume>(CLSCTX_ALL, None)
+                .map_err(|err| describe_error("Falha ao ativar controle de volume", err))?;
+            let meter = device
+                .Activate::<IAudioMeterInformation>(CLSCTX_ALL, None)
+                .map_err(|err| describe_error("Falha ao ativar medidor de pico", err))?;
+
+            // Semeia o alvo de restauracao com o estado atual antes de registrar
+            // a callback, para que a primeira restauracao tenha valores
+            // coerentes.
+            store_restore_target(
+                endpoint.GetMasterVolumeLevelScalar().map_err(|err| {
+                    describe_error("Falha ao ler o volume mestre", err)
+                })?,
+                endpoint
+                    .GetMute()
+                    .map_err(|err| describe_error("Falha ao ler o estado de mudo", err))?
+                    .as_bool(),
+            );
+
+            let callback = VolumeNotificationGuard::register(&endpoint)
+                .map_err(|err| describe_error("Falha ao registrar a callback de volume", err))?;
+            let timer = WaitableTimer::periodic(POLL_INTERVAL)
+                .map_err(|err| describe_error("Falha ao criar o timer de verificacao", err))?;
+
+            Ok(Self {
+                endpoint,
+                meter,
+                timer,
+                _callback: callback,
+                _com: com,
+            })
+        }
+    }
+}
+
+impl AudioController for WindowsAudio {
+    fn current_volume(&self) -> Result<f32, String> {
+        unsafe { self.endpoint.GetMasterVolumeLevelScalar() }
+            .map_err(|err| describe_error("Falha ao ler o volume mestre", err))
+    }
+
+    fn set_volume(&self, level: f32) -> Result<(), String> {
+        let clamped = level.clamp(0.0, 1.0);
+        unsafe {
+            self.endpoint
+                .SetMasterVolumeLevelScalar(clamped, &SOUND_INACTIVITY_CONTEXT)
+        }
+        .map_err(|err| describe_error("Falha ao ajustar o volume mestre", err))
+    }
+
+    fn is_muted(&self) -> Result<bool, String> {
+        unsafe { self.endpoint.GetMute() }
+            .map(|muted| muted.as_bool())
+            .map_err(|err| describe_error("Falha ao ler o estado de mudo", err))
+    }
+
+    fn set_mute(&self, mute: bool) -> Result<(), String> {
+        unsafe { self.endpoint.SetMute(mute, &SOUND_INACTIVITY_CONTEXT) }
+            .map_err(|err| describe_error("Falha ao alterar o estado de mudo", err))
+    }
+
+    fn peak_value(&self) -> Result<f32, String> {
+        unsafe { self.meter.GetPeakValue() }
+            .map_err(|err| describe_error("Falha ao ler o pico de reproducao", err))
+    }
+
+    fn restore_target(&self) -> Result<Option<(f32, bool)>, String> {
+        Ok(Some(load_restore_target()))
+    }
+
+    fn wait_next_poll(&self) -> Result<(), String> {
+        self.timer
+            .wait()
+            .map_err(|err| describe_error("Falha ao aguardar o proximo ciclo", err))
+    }
+}
+
+/// Tempo desde a ultima interacao do usuario via `GetLastInputInfo`.
+pub fn idle_time() -> Result<Duration, String> {
+    unsafe {
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+
+        if !GetLastInputInfo(&mut info).as_bool() {
+            return Err(describe_error(
+                "Falha ao obter o tempo de inatividade",
+                WinError::from_win32(),
+            ));
+        }
+
+        let current = GetTickCount64();
+        let last_input = u64::from(info.dwTime);
+        let idle_ms = current.saturating_sub(last_input);
+
+        Ok(Duration::from_millis(idle_ms))
+    }
+}
+
+fn store_restore_target(volume: f32, mute: bool) {
+    RESTORE_VOLUME_BITS.store(volume.to_bits(), Ordering::Relaxed);
+    RESTORE_MUTE_STATE.store(mute, Ordering::Relaxed);
+}
+
+fn load_restore_target() -> (f32, bool) {
+    (
+        f32::from_bits(RESTORE_VOLUME_BITS.load(Ordering::Relaxed)),
+        RESTORE_MUTE_STATE.load(Ordering::Relaxed),
+    )
+}
+
+// Callback COM chamada pelo WASAPI a cada mudanca externa de volume/mudo.
+#[implement(IAudioEndpointVolumeCallback)]
+struct VolumeNotification;
+
+impl IAudioEndpointVolumeCallback_Impl for VolumeNotification_Impl {
+    fn OnNotify(&self, pnotify: *mut AUDIO_VOLUME_NOTIFICATION_DATA) -> WinResult<()> {
+        if pnotify.is_null() {
+            return Ok(());
+        }
+
+        let data = unsafe { &*pnotify };
+
+        // Ignora as mudancas originadas por nos mesmos; so o que o usuario altera
+        // deve redefinir o alvo de restauracao.
+        if data.guidEventContext != SOUND_INACTIVITY_CONTEXT {
+            store_restore_target(data.fMasterVolume, data.bMuted.as_bool());
+        }
+
+        Ok(())
+    }
+}
+
+// Mantem o registro da callback vivo e garante o unregister no Drop.
+struct VolumeNotificationGuard {
+    endpoint: IAudioEndpointVolume,
+    callback: IAudioEndpointVolumeCallback,
+}
+
+impl VolumeNotificationGuard {
+    fn register(endpoint: &IAudioEndpointVolume) -> WinResult<Self> {
+        let callback: IAudioEndpointVolumeCallback = VolumeNotification.into();
+        unsafe {
+            endpoint.RegisterControlChangeNotify(&callback)?;
+        }
+
+        Ok(Self {
+            endpoint: endpoint.clone(),
+            callback,
+        })
+    }
+}
+
+impl Drop for VolumeNotificationGuard {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = self.endpoint.UnregisterControlChangeNotify(&self.callback);
+        }
+    }
+}
+
+// Timer waitable periodico que despacha as callbacks COM enquanto espera.
+struct WaitableTimer {
+    handle: HANDLE,
+}
+
+impl WaitableTimer {
+    fn periodic(interval: Duration) -> WinResult<Self> {
+        let handle = unsafe { CreateWaitableTimerW(None, false, None)? };
+
+        // Primeiro disparo relativo (em unidades de 100ns, valor negativo) e
+        // periodo em milissegundos para os disparos seguintes.
+        let due_time: i64 = -((interval.as_millis() as i64).max(1) * 10_000);
+        let period_ms = interval.as_millis().min(i32::MAX as u128) as i32;
+
+        unsafe {
+            SetWaitableTimer(handle, &due_time, period_ms, None, None, false)?;
+        }
+
+        Ok(Self { handle })
+    }
+
+    fn wait(&self) -> WinResult<()> {
+        let handles = [self.handle];
+        let mut index = 0u32;
+        unsafe {
+            CoWaitForMultipleObjects(CWMO_DISPATCH_CALLS.0 as u32, INFINITE, &handles, &mut index)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for WaitableTimer {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = windows::Win32::Foundation::CloseHandle(self.handle);
+        }
+    }
+}
+
+struct ComGuard {
+    should_uninit: bool,
+}
+
+impl ComGuard {
+    unsafe fn new() -> Result<Self, WinError> {
+        let hr = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+
+        if hr.is_err() {
+            return Err(WinError::from(hr));
+        }
+
+        Ok(Self {
+            should_uninit: true,
+        })
+    }
+}
+
+impl Drop for ComGuard {
+    fn drop(&mut self) {
+        if self.should_uninit {
+            unsafe {
+                CoUninitialize();
+            }
+        }
+    }
+}
+
+fn describe_error(context: &str, err: WinError) -> String {
+    let message = err.message();
+
+    if message.is_empty() {
+        format!("{context}: codigo 0x{:08X}", err.code().0 as u32)
+    } else {
+        format!("{context}: {message}")
+    }
+}
@@ -0,0 +1,93 @@
+use std::{fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+const CONFIG_DIR: &str = "sound-inactivity";
+const CONFIG_FILE: &str = "settings.toml";
+const DEFAULT_THRESHOLD_SECS: u64 = 5 * 60;
+const DEFAULT_FADE_DURATION_MS: u64 = 2_000;
+const DEFAULT_PEAK_EPSILON: f32 = 0.01;
+
+// Como o audio e atenuado ao cruzar o limite de inatividade.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MuteMode {
+    /// Silencia o dispositivo com `SetMute(true)`.
+    Mute,
+    /// Reduz o volume ate o nivel `quiet_level`.
+    Lower,
+    /// Faz uma transicao suave ate `quiet_level` ao longo de `fade_duration_ms`.
+    Fade,
+}
+
+impl Default for MuteMode {
+    fn default() -> Self {
+        MuteMode::Mute
+    }
+}
+
+// Configuracao persistida entre sessoes. O arquivo TOML e a fonte da verdade; os
+// atomicos do monitor sao apenas um cache de leitura no caminho quente.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub threshold_secs: u64,
+    pub mode: MuteMode,
+    pub quiet_level: f32,
+    pub fade_duration_ms: u64,
+    pub notifications: bool,
+    pub require_silence: bool,
+    pub peak_epsilon: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            threshold_secs: DEFAULT_THRESHOLD_SECS,
+            mode: MuteMode::default(),
+            quiet_level: 0.0,
+            fade_duration_ms: DEFAULT_FADE_DURATION_MS,
+            notifications: true,
+            require_silence: false,
+            peak_epsilon: DEFAULT_PEAK_EPSILON,
+        }
+    }
+}
+
+fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|base| base.join(CONFIG_DIR).join(CONFIG_FILE))
+}
+
+/// Carrega as configuracoes do disco, caindo para os padroes se o arquivo nao
+/// existir ou estiver corrompido.
+pub fn load() -> Settings {
+    let Some(path) = config_path() else {
+        return Settings::default();
+    };
+
+    match fs::read_to_string(&path) {
+        Ok(contents) => toml::from_str(&contents).unwrap_or_else(|err| {
+            eprintln!("[sound-inactive] configuracao invalida ({err}); usando padroes");
+            Settings::default()
+        }),
+        Err(_) => Settings::default(),
+    }
+}
+
+/// Grava as configuracoes no disco, criando o diretorio de configuracao se
+/// necessario.
+pub fn save(settings: &Settings) -> Result<(), String> {
+    let path = config_path().ok_or("Nao foi possivel localizar o diretorio de configuracao.")?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| format!("Falha ao criar o diretorio de configuracao: {err}"))?;
+    }
+
+    let contents = toml::to_string_pretty(settings)
+        .map_err(|err| format!("Falha ao serializar a configuracao: {err}"))?;
+    fs::write(&path, contents)
+        .map_err(|err| format!("Falha ao gravar a configuracao: {err}"))?;
+
+    Ok(())
+}
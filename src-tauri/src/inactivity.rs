@@ -1,35 +1,56 @@
 use std::{
     sync::{
-        atomic::{AtomicU64, Ordering},
+        atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicU8, Ordering},
         OnceLock,
     },
-    thread,
     time::Duration,
 };
-use windows::{
-    core::{Error as WinError, Result as WinResult},
-    Win32::{
-        Media::Audio::{
-            eConsole, eRender, Endpoints::IAudioEndpointVolume, IMMDeviceEnumerator,
-            MMDeviceEnumerator,
-        },
-        System::Com::{
-            CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_ALL, COINIT_APARTMENTTHREADED,
-        },
-        System::SystemInformation::GetTickCount64,
-        UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO},
-    },
-};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::backend::{self, AudioController};
+use crate::config::{self, MuteMode, Settings};
+
+const ACTIVITY_EVENT: &str = "sound-inactivity://activity";
+
+// Evento encaminhado a webview para que a bandeja exiba a atividade recente.
+#[derive(Clone, Serialize)]
+struct ActivityEvent {
+    action: &'static str,
+    message: String,
+}
 
 const DEFAULT_INACTIVITY_THRESHOLD_SECS: u64 = 5 * 60;
-const POLL_INTERVAL: Duration = Duration::from_secs(5);
-const QUIET_VOLUME_LEVEL: f32 = 0.0;
+const DEFAULT_FADE_DURATION_MS: u64 = 2_000;
 const VOLUME_EPSILON: f32 = 0.02;
+const REQUIRED_SILENT_POLLS: u32 = 3;
+// Duracao de cada passo do fade; passos curtos mantem a rampa suave e permitem
+// abortar rapidamente quando o usuario volta a interagir.
+const FADE_STEP: Duration = Duration::from_millis(50);
 
 static MONITOR: OnceLock<Result<(), String>> = OnceLock::new();
 static INACTIVITY_THRESHOLD_SECS: AtomicU64 = AtomicU64::new(DEFAULT_INACTIVITY_THRESHOLD_SECS);
 
-pub fn start_monitor() -> Result<(), String> {
+// Quando ativado, so abaixamos o audio apos o pico de reproducao ficar abaixo de
+// `PEAK_EPSILON_BITS` por `REQUIRED_SILENT_POLLS` ciclos seguidos, ou seja,
+// ninguem esta de fato ouvindo nada.
+static REQUIRE_SILENCE: AtomicBool = AtomicBool::new(false);
+static PEAK_EPSILON_BITS: AtomicU32 = AtomicU32::new(0x3C23_D70A); // 0.01
+
+// Cache das configuracoes persistidas (modo, nivel alvo e notificacoes). O
+// arquivo TOML continua sendo a fonte da verdade; estes atomicos evitam I/O no
+// caminho quente do monitor.
+const MODE_MUTE: u8 = 0;
+const MODE_LOWER: u8 = 1;
+const MODE_FADE: u8 = 2;
+static MODE: AtomicU8 = AtomicU8::new(MODE_MUTE);
+static QUIET_LEVEL_BITS: AtomicU32 = AtomicU32::new(0); // 0.0
+static FADE_DURATION_MS: AtomicU64 = AtomicU64::new(DEFAULT_FADE_DURATION_MS);
+static NOTIFICATIONS: AtomicBool = AtomicBool::new(true);
+
+pub fn start_monitor(app: AppHandle) -> Result<(), String> {
     println!(
         "[sound-inactive] iniciando monitor de inatividade sonora (threshold atual: {} segundos)...",
         inactivity_threshold().as_secs()
@@ -38,8 +59,8 @@ pub fn start_monitor() -> Result<(), String> {
         .get_or_init(|| {
             std::thread::Builder::new()
                 .name("sound-inactive-monitor".into())
-                .spawn(|| {
-                    if let Err(err) = run_monitor() {
+                .spawn(move || {
+                    if let Err(err) = run_monitor(app) {
                         eprintln!("[sound-inactive] monitor encerrado com erro: {err}");
                     }
                 })
@@ -49,6 +70,24 @@ pub fn start_monitor() -> Result<(), String> {
         .clone()
 }
 
+/// Carrega as configuracoes persistidas para o cache atomico. Deve ser chamada
+/// antes de `start_monitor` para que o monitor comece com os valores salvos.
+pub fn apply_settings(settings: &Settings) {
+    INACTIVITY_THRESHOLD_SECS.store(settings.threshold_secs.max(1), Ordering::Relaxed);
+    MODE.store(mode_to_u8(settings.mode), Ordering::Relaxed);
+    QUIET_LEVEL_BITS.store(
+        settings.quiet_level.clamp(0.0, 1.0).to_bits(),
+        Ordering::Relaxed,
+    );
+    FADE_DURATION_MS.store(settings.fade_duration_ms.max(1), Ordering::Relaxed);
+    NOTIFICATIONS.store(settings.notifications, Ordering::Relaxed);
+    REQUIRE_SILENCE.store(settings.require_silence, Ordering::Relaxed);
+    PEAK_EPSILON_BITS.store(
+        settings.peak_epsilon.clamp(0.0, 1.0).to_bits(),
+        Ordering::Relaxed,
+    );
+}
+
 pub fn set_inactivity_threshold(duration: Duration) -> Result<(), String> {
     if duration.is_zero() {
         return Err("O tempo de inatividade deve ser maior que zero.".into());
@@ -61,138 +100,283 @@ pub fn set_inactivity_threshold(duration: Duration) -> Result<(), String> {
         secs
     );
 
-    Ok(())
+    persist()
+}
+
+pub fn set_playback_gate(require_silence: bool, peak_epsilon: Option<f32>) -> Result<(), String> {
+    if let Some(epsilon) = peak_epsilon {
+        if !(0.0..=1.0).contains(&epsilon) {
+            return Err("O pico minimo deve estar entre 0.0 e 1.0.".into());
+        }
+        PEAK_EPSILON_BITS.store(epsilon.to_bits(), Ordering::Relaxed);
+    }
+
+    REQUIRE_SILENCE.store(require_silence, Ordering::Relaxed);
+    println!(
+        "[sound-inactive] exigir silencio: {require_silence} (pico minimo: {})",
+        peak_epsilon()
+    );
+
+    persist()
+}
+
+pub fn set_mode(mode: MuteMode) -> Result<(), String> {
+    MODE.store(mode_to_u8(mode), Ordering::Relaxed);
+    println!("[sound-inactive] modo atualizado para {mode:?}");
+    persist()
+}
+
+pub fn set_quiet_level(level: f32) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&level) {
+        return Err("O nivel alvo deve estar entre 0.0 e 1.0.".into());
+    }
+    QUIET_LEVEL_BITS.store(level.to_bits(), Ordering::Relaxed);
+    println!("[sound-inactive] nivel alvo atualizado para {level}");
+    persist()
+}
+
+pub fn set_fade_duration(duration: Duration) -> Result<(), String> {
+    if duration.is_zero() {
+        return Err("A duracao do fade deve ser maior que zero.".into());
+    }
+    let ms = (duration.as_millis().min(u64::MAX as u128) as u64).max(1);
+    FADE_DURATION_MS.store(ms, Ordering::Relaxed);
+    println!("[sound-inactive] duracao do fade atualizada para {ms} ms");
+    persist()
+}
+
+pub fn set_notifications(enabled: bool) -> Result<(), String> {
+    NOTIFICATIONS.store(enabled, Ordering::Relaxed);
+    println!("[sound-inactive] notificacoes: {enabled}");
+    persist()
+}
+
+// Reconstroi as configuracoes a partir do cache e as grava no disco.
+fn persist() -> Result<(), String> {
+    config::save(&current_settings())
 }
 
-fn run_monitor() -> Result<(), String> {
-    unsafe {
-        let _com =
-            ComGuard::new().map_err(|err| describe_error("Falha ao inicializar COM", err))?;
-        let enumerator: IMMDeviceEnumerator =
-            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
-                .map_err(|err| describe_error("Falha ao criar enumerador de dispositivos", err))?;
-        let device = enumerator
-            .GetDefaultAudioEndpoint(eRender, eConsole)
-            .map_err(|err| describe_error("Falha ao obter dispositivo de audio padrao", err))?;
-        let endpoint = device
-            .Activate::<IAudioEndpointVolume>(CLSCTX_ALL, None)
-            .map_err(|err| describe_error("Falha ao ativar controle de volume", err))?;
-
-        monitor_loop(endpoint).map_err(|err| describe_error("Falha durante monitoramento", err))
+fn current_settings() -> Settings {
+    Settings {
+        threshold_secs: INACTIVITY_THRESHOLD_SECS.load(Ordering::Relaxed),
+        mode: current_mode(),
+        quiet_level: quiet_level(),
+        fade_duration_ms: FADE_DURATION_MS.load(Ordering::Relaxed),
+        notifications: NOTIFICATIONS.load(Ordering::Relaxed),
+        require_silence: REQUIRE_SILENCE.load(Ordering::Relaxed),
+        peak_epsilon: peak_epsilon(),
     }
 }
 
-fn monitor_loop(endpoint: IAudioEndpointVolume) -> WinResult<()> {
+fn run_monitor(app: AppHandle) -> Result<(), String> {
+    let controller = backend::new_controller()?;
+    monitor_loop(controller.as_ref(), &app)
+}
+
+fn monitor_loop(controller: &dyn AudioController, app: &AppHandle) -> Result<(), String> {
     let mut lowered = false;
-    let mut previous_volume = 1.0;
-    let mut previous_mute_state = false;
+    let mut silent_polls = 0u32;
+    // Alvo de restauracao usado quando o backend nao rastreia mudancas externas.
+    let mut captured = (1.0f32, false);
 
     loop {
         let threshold = inactivity_threshold();
-        let idle = idle_time()?;
+        let idle = backend::idle_time()?;
 
         if idle >= threshold {
-            if !lowered {
-                let current = current_volume(&endpoint)?;
-                let is_muted = unsafe { endpoint.GetMute()?.as_bool() };
-                previous_volume = current;
-                previous_mute_state = is_muted;
-
-                if !is_muted {
-                    unsafe {
-                        endpoint.SetMute(true, std::ptr::null())?;
-                    }
+            // Conta os ciclos consecutivos de silencio; so abaixamos quando o
+            // gate de reproducao permite (ou quando ele esta desligado).
+            if REQUIRE_SILENCE.load(Ordering::Relaxed) {
+                let peak = controller.peak_value()?;
+                if peak < peak_epsilon() {
+                    silent_polls = silent_polls.saturating_add(1);
+                } else {
+                    silent_polls = 0;
                 }
+            }
 
-                if (current - QUIET_VOLUME_LEVEL).abs() > VOLUME_EPSILON {
-                    set_volume(&endpoint, QUIET_VOLUME_LEVEL)?;
+            if !lowered && playback_gate_open(silent_polls) {
+                captured = match controller.restore_target()? {
+                    Some(target) => target,
+                    None => (controller.current_volume()?, controller.is_muted()?),
+                };
+                // `lower_audio` retorna `false` quando um fade e interrompido
+                // pela volta do usuario; nesse caso ja revertemos e seguimos
+                // como se nunca tivessemos abaixado.
+                let was_lowered = lower_audio(controller, captured)?;
+                if was_lowered {
+                    let minutes = threshold.as_secs() / 60;
+                    announce(
+                        app,
+                        "silenced",
+                        "Som inativo",
+                        format!("Audio silenciado apos {minutes} minutos de inatividade"),
+                    );
                 }
-
-                lowered = true;
+                lowered = was_lowered;
             }
-        } else if lowered {
-            set_volume(&endpoint, previous_volume)?;
+        } else {
+            silent_polls = 0;
 
-            if !previous_mute_state {
-                unsafe {
-                    endpoint.SetMute(false, std::ptr::null())?;
+            if lowered {
+                // Prefere o alvo vivo do backend (atualizado mesmo enquanto
+                // abaixado); caindo para o valor capturado ao abaixar.
+                let (target_volume, target_mute) =
+                    controller.restore_target()?.unwrap_or(captured);
+
+                restore_audio(controller, target_volume)?;
+
+                if !target_mute {
+                    controller.set_mute(false)?;
                 }
-            }
 
-            lowered = false;
+                announce(app, "restored", "Som restaurado", "Audio restaurado".into());
+                lowered = false;
+            }
         }
 
-        thread::sleep(POLL_INTERVAL);
+        // Aguarda o proximo ciclo. O backend do Windows despacha as callbacks de
+        // volume durante a espera, mas nenhum backend retorna antes do intervalo
+        // de verificacao.
+        controller.wait_next_poll()?;
     }
 }
 
-fn inactivity_threshold() -> Duration {
-    let secs = INACTIVITY_THRESHOLD_SECS.load(Ordering::Relaxed).max(1);
-    Duration::from_secs(secs)
-}
+// Emite uma notificacao de desktop e encaminha o evento a webview, respeitando a
+// flag persistida de notificacoes. Falhas sao apenas registradas: a transicao de
+// audio ja ocorreu e nao deve ser abortada por um erro de UI.
+fn announce(app: &AppHandle, action: &'static str, title: &str, message: String) {
+    // A notificacao de desktop e opcional; o evento da webview e sempre enviado
+    // para que a bandeja mantenha o historico de atividade recente.
+    if NOTIFICATIONS.load(Ordering::Relaxed) {
+        if let Err(err) = app
+            .notification()
+            .builder()
+            .title(title)
+            .body(&message)
+            .show()
+        {
+            eprintln!("[sound-inactive] falha ao exibir notificacao: {err}");
+        }
+    }
 
-fn idle_time() -> WinResult<Duration> {
-    unsafe {
-        let mut info = LASTINPUTINFO {
-            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
-            dwTime: 0,
-        };
+    let payload = ActivityEvent { action, message };
+    if let Err(err) = app.emit(ACTIVITY_EVENT, payload) {
+        eprintln!("[sound-inactive] falha ao encaminhar evento a webview: {err}");
+    }
+}
 
-        if !GetLastInputInfo(&mut info).as_bool() {
-            return Err(WinError::from_win32());
+// Atenua o audio conforme o modo configurado. Retorna `true` quando o audio
+// ficou de fato abaixado e `false` quando um fade foi interrompido pela volta do
+// usuario (nesse caso o volume ja foi revertido para `restore`).
+fn lower_audio(controller: &dyn AudioController, restore: (f32, bool)) -> Result<bool, String> {
+    match current_mode() {
+        MuteMode::Mute => {
+            if !controller.is_muted()? {
+                controller.set_mute(true)?;
+            }
+        }
+        MuteMode::Lower => {
+            let target = quiet_level();
+            // So reduzimos: se o volume atual ja esta no nivel alvo ou abaixo,
+            // nao elevamos o audio ao ficar inativo.
+            if controller.current_volume()? - target > VOLUME_EPSILON {
+                controller.set_volume(target)?;
+            }
         }
+        MuteMode::Fade => {
+            let from = controller.current_volume()?;
+            // So reduzimos: se ja estamos no nivel alvo ou abaixo, nao ha
+            // descida a executar.
+            if from <= quiet_level() {
+                return Ok(true);
+            }
+            if !fade_to(controller, from, quiet_level(), true)? {
+                // Interrompido no meio da descida: reverte imediatamente para o
+                // alvo de restauracao em vez de concluir a rampa.
+                let current = controller.current_volume()?;
+                fade_to(controller, current, restore.0, false)?;
+                return Ok(false);
+            }
+        }
+    }
 
-        let current = GetTickCount64();
-        let last_input = u64::from(info.dwTime);
-        let idle_ms = current.saturating_sub(last_input);
+    Ok(true)
+}
 
-        Ok(Duration::from_millis(idle_ms))
+// Restaura o audio apos atividade. No modo fade sobe gradualmente; nos demais,
+// retorna direto ao alvo.
+fn restore_audio(controller: &dyn AudioController, target: f32) -> Result<(), String> {
+    if current_mode() == MuteMode::Fade {
+        let from = controller.current_volume()?;
+        fade_to(controller, from, target, false)?;
+    } else {
+        controller.set_volume(target)?;
     }
-}
 
-fn current_volume(endpoint: &IAudioEndpointVolume) -> WinResult<f32> {
-    unsafe { endpoint.GetMasterVolumeLevelScalar() }
+    Ok(())
 }
 
-fn set_volume(endpoint: &IAudioEndpointVolume, level: f32) -> WinResult<()> {
-    let clamped = level.clamp(0.0, 1.0);
-    unsafe { endpoint.SetMasterVolumeLevelScalar(clamped, std::ptr::null()) }
+// Rampa o volume de `from` ate `to` em passos curtos. Quando `abort_on_activity`
+// e verdadeiro (descida), interrompe e retorna `false` assim que o usuario volta
+// a interagir, para que a descida possa ser revertida.
+fn fade_to(
+    controller: &dyn AudioController,
+    from: f32,
+    to: f32,
+    abort_on_activity: bool,
+) -> Result<bool, String> {
+    let duration = fade_duration();
+    let steps = (duration.as_millis() / FADE_STEP.as_millis().max(1)).max(1) as u32;
+
+    for step in 1..=steps {
+        if abort_on_activity && backend::idle_time()? < inactivity_threshold() {
+            return Ok(false);
+        }
+
+        let progress = step as f32 / steps as f32;
+        controller.set_volume(from + (to - from) * progress)?;
+        std::thread::sleep(FADE_STEP);
+    }
+
+    Ok(true)
 }
 
-struct ComGuard {
-    should_uninit: bool,
+fn inactivity_threshold() -> Duration {
+    let secs = INACTIVITY_THRESHOLD_SECS.load(Ordering::Relaxed).max(1);
+    Duration::from_secs(secs)
 }
 
-impl ComGuard {
-    unsafe fn new() -> Result<Self, WinError> {
-        let hr = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+fn peak_epsilon() -> f32 {
+    f32::from_bits(PEAK_EPSILON_BITS.load(Ordering::Relaxed))
+}
 
-        if hr.is_err() {
-            return Err(WinError::from(hr));
-        }
+// O gate de reproducao esta aberto quando nao exigimos silencio ou quando o
+// audio ficou abaixo do pico minimo por ciclos suficientes.
+fn playback_gate_open(silent_polls: u32) -> bool {
+    !REQUIRE_SILENCE.load(Ordering::Relaxed) || silent_polls >= REQUIRED_SILENT_POLLS
+}
 
-        Ok(Self {
-            should_uninit: true,
-        })
+fn current_mode() -> MuteMode {
+    match MODE.load(Ordering::Relaxed) {
+        MODE_LOWER => MuteMode::Lower,
+        MODE_FADE => MuteMode::Fade,
+        _ => MuteMode::Mute,
     }
 }
 
-impl Drop for ComGuard {
-    fn drop(&mut self) {
-        if self.should_uninit {
-            unsafe {
-                CoUninitialize();
-            }
-        }
+fn mode_to_u8(mode: MuteMode) -> u8 {
+    match mode {
+        MuteMode::Mute => MODE_MUTE,
+        MuteMode::Lower => MODE_LOWER,
+        MuteMode::Fade => MODE_FADE,
     }
 }
 
-fn describe_error(context: &str, err: WinError) -> String {
-    let message = err.message();
+fn quiet_level() -> f32 {
+    f32::from_bits(QUIET_LEVEL_BITS.load(Ordering::Relaxed))
+}
 
-    if message.is_empty() {
-        format!("{context}: codigo 0x{:08X}", err.code().0 as u32)
-    } else {
-        format!("{context}: {message}")
-    }
+fn fade_duration() -> Duration {
+    Duration::from_millis(FADE_DURATION_MS.load(Ordering::Relaxed).max(1))
 }
@@ -1,4 +1,3 @@
-#[cfg(target_os = "windows")]
 use std::time::Duration;
 use tauri::{
     menu::{Menu, MenuItem},
@@ -6,24 +5,22 @@ use tauri::{
     Manager,
 };
 
-#[cfg(target_os = "windows")]
+mod backend;
+mod config;
 mod inactivity;
 
-fn init_sound_inactivity_monitor() {
+fn init_sound_inactivity_monitor(app: tauri::AppHandle) {
     std::thread::Builder::new()
         .name("sound-inactive-init".into())
-        .spawn(|| {
+        .spawn(move || {
             println!("Iniciando monitoramento de inatividade sonora...");
-            #[cfg(target_os = "windows")]
-            if let Err(err) = inactivity::start_monitor() {
+            let settings = config::load();
+            inactivity::apply_settings(&settings);
+            if let Err(err) = inactivity::start_monitor(app) {
                 eprintln!(
                     "[sound-inactive] falha ao iniciar monitoramento de inatividade sonora: {err}"
                 );
             }
-            #[cfg(not(target_os = "windows"))]
-            {
-                eprintln!("Funcionalidade disponivel apenas no Windows.");
-            }
         })
         .expect("nao foi possivel criar a thread de inicializacao do monitoramento");
 }
@@ -36,24 +33,41 @@ fn set_sound_inactivity_timeout(minutes: Option<u64>) -> Result<(), String> {
         return Err("O tempo de inatividade deve ser maior que zero.".into());
     }
 
-    #[cfg(target_os = "windows")]
-    {
-        let duration = Duration::from_secs(minutes.saturating_mul(60));
-        return inactivity::set_inactivity_threshold(duration);
-    }
+    let duration = Duration::from_secs(minutes.saturating_mul(60));
+    inactivity::set_inactivity_threshold(duration)
+}
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        let _ = minutes;
-        Err("Funcionalidade disponivel apenas no Windows.".into())
-    }
+#[tauri::command]
+fn set_sound_playback_gate(require_silence: bool, peak_epsilon: Option<f32>) -> Result<(), String> {
+    inactivity::set_playback_gate(require_silence, peak_epsilon)
+}
+
+#[tauri::command]
+fn set_sound_inactivity_mode(mode: config::MuteMode) -> Result<(), String> {
+    inactivity::set_mode(mode)
+}
+
+#[tauri::command]
+fn set_sound_quiet_level(level: f32) -> Result<(), String> {
+    inactivity::set_quiet_level(level)
+}
+
+#[tauri::command]
+fn set_sound_fade_duration(milliseconds: u64) -> Result<(), String> {
+    inactivity::set_fade_duration(Duration::from_millis(milliseconds))
+}
+
+#[tauri::command]
+fn set_sound_notifications(enabled: bool) -> Result<(), String> {
+    inactivity::set_notifications(enabled)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        .plugin(tauri_plugin_notification::init())
         .setup(|app| {
-            init_sound_inactivity_monitor();
+            init_sound_inactivity_monitor(app.handle().clone());
 
             use tauri_plugin_autostart::MacosLauncher;
 
@@ -116,7 +130,14 @@ pub fn run() {
                 api.prevent_close();
             }
         })
-        .invoke_handler(tauri::generate_handler![set_sound_inactivity_timeout])
+        .invoke_handler(tauri::generate_handler![
+            set_sound_inactivity_timeout,
+            set_sound_playback_gate,
+            set_sound_inactivity_mode,
+            set_sound_quiet_level,
+            set_sound_fade_duration,
+            set_sound_notifications
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }